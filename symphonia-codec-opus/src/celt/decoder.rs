@@ -0,0 +1,107 @@
+use std::f32::consts::PI;
+use super::mdct::Mdct;
+
+/// Drives CELT's frequency-to-time synthesis: the inverse MDCT followed by low-overlap
+/// windowing and overlap-add, per RFC 6716 Section 4.3.7.
+///
+/// Each call to `synthesize` consumes `n/2` frequency-domain coefficients for a block of size
+/// `n` and emits `n/2` new time-domain samples, carrying the other half of the transform
+/// forward as the overlap tail for the next call.
+pub struct Decoder {
+    mdct: Mdct,
+    /// Length of the overlap region; equal to `n/2`, the hop size between successive frames.
+    overlap: usize,
+    /// The Vorbis-style window `sin(pi/2 * sin^2(pi/(2*overlap) * (i + 0.5)))`, ascending.
+    window: Vec<f32>,
+    /// The windowed second half of the previous frame, waiting to be added into this frame's
+    /// first half.
+    history: Vec<f32>,
+}
+
+impl Decoder {
+    /// Creates a CELT synthesis context for a block of `n` samples (CELT's short/long block
+    /// sizes are 120, 240, 480, or 960 samples at the 48 kHz CELT rate).
+    pub fn new(n: usize) -> Self {
+        assert!(n % 4 == 0, "CELT block size must be a multiple of 4");
+
+        let overlap = n / 2;
+        let window = (0..overlap)
+            .map(|i| {
+                let inner = (PI / (2.0 * overlap as f32) * (i as f32 + 0.5)).sin();
+                (PI / 2.0 * inner * inner).sin()
+            })
+            .collect();
+
+        return Decoder {
+            mdct: Mdct::new(n),
+            overlap,
+            window,
+            history: vec![0.0; overlap],
+        };
+    }
+
+    /// Returns the number of new samples each call to `synthesize` produces.
+    pub fn hop_size(&self) -> usize {
+        return self.overlap;
+    }
+
+    /// Synthesizes the next `hop_size()` time-domain samples from this frame's `n/2`
+    /// frequency-domain coefficients, overlap-adding the windowed start of this frame with the
+    /// windowed tail carried over from the previous call.
+    pub fn synthesize(&mut self, freq: &[f32], out: &mut [f32]) {
+        assert_eq!(out.len(), self.overlap, "synthesize output must be exactly one hop long");
+
+        let mut time = vec![0.0f32; self.mdct.size()];
+        self.mdct.imdct(freq, &mut time);
+
+        for i in 0..self.overlap {
+            out[i] = time[i] * self.window[i] + self.history[i];
+        }
+
+        for i in 0..self.overlap {
+            self.history[i] = time[self.overlap + i] * self.window[self.overlap - 1 - i];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_satisfies_princen_bradley() {
+        // sin^2(pi/2 * x) + cos^2(pi/2 * x) == 1 guarantees the ascending and descending halves
+        // of the window sum to unity power, which is what makes overlap-add lossless.
+        let decoder = Decoder::new(120);
+        for i in 0..decoder.overlap {
+            let asc = decoder.window[i];
+            let desc = decoder.window[decoder.overlap - 1 - i];
+            let sum = asc * asc + desc * desc;
+            assert!((sum - 1.0).abs() < 1e-5, "window[{i}]^2 + window[mirror]^2 != 1: {sum}");
+        }
+    }
+
+    #[test]
+    fn test_synthesize_emits_one_hop_per_call() {
+        let mut decoder = Decoder::new(120);
+        let freq = vec![0.0f32; 60];
+        let mut out = vec![0.0f32; decoder.hop_size()];
+
+        decoder.synthesize(&freq, &mut out);
+
+        assert_eq!(out.len(), 60);
+        assert!(out.iter().all(|s| s.is_finite()));
+    }
+
+    #[test]
+    fn test_silence_in_silence_out() {
+        let mut decoder = Decoder::new(240);
+        let freq = vec![0.0f32; 120];
+        let mut out = vec![0.0f32; decoder.hop_size()];
+
+        decoder.synthesize(&freq, &mut out);
+        decoder.synthesize(&freq, &mut out);
+
+        assert!(out.iter().all(|&s| s == 0.0));
+    }
+}
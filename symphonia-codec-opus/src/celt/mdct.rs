@@ -0,0 +1,169 @@
+use std::f32::consts::PI;
+
+/// An inverse MDCT context for a fixed transform size `n`.
+///
+/// Computes the inverse Modified Discrete Cosine Transform directly from its definition (the
+/// same type-IV-DCT-derived formula CELT's "fast MDCT via FFT" decomposition in the reference
+/// implementation's `mdct.c` is equivalent to, just without that decomposition's `O(n log n)`
+/// speedup): each of the `n` output samples is a cosine-weighted sum of all `n/2` input
+/// coefficients.
+///
+/// https://datatracker.ietf.org/doc/html/rfc6716#section-4.3
+pub struct Mdct {
+    n: usize,
+}
+
+impl Mdct {
+    pub fn new(n: usize) -> Self {
+        assert!(n % 4 == 0, "CELT MDCT size must be a multiple of 4");
+
+        return Mdct { n };
+    }
+
+    pub fn size(&self) -> usize {
+        return self.n;
+    }
+
+    /// Runs the inverse MDCT over `freq`, a half-length (`n/2`) array of frequency-domain
+    /// coefficients, producing `n` time-domain samples in `out`, per:
+    ///
+    /// `out[i] = sum_{k=0}^{n/2-1} freq[k] * cos((2*pi/n) * (i + 0.5 + n/4) * (k + 0.5))`
+    pub fn imdct(&self, freq: &[f32], out: &mut [f32]) {
+        assert_eq!(freq.len(), self.n / 2, "imdct input must hold n/2 coefficients");
+        assert_eq!(out.len(), self.n, "imdct output must hold n samples");
+
+        let n = self.n as f32;
+
+        for (i, sample) in out.iter_mut().enumerate() {
+            let mut sum = 0.0f32;
+            for (k, &coeff) in freq.iter().enumerate() {
+                let angle = (2.0 * PI / n) * (i as f32 + 0.5 + n / 4.0) * (k as f32 + 0.5);
+                sum += coeff * angle.cos();
+            }
+            *sample = sum;
+        }
+    }
+}
+
+/// The forward MDCT, the dual of `Mdct::imdct`: `n` time-domain samples to `n/2`
+/// frequency-domain coefficients. CELT itself only ever runs the inverse transform (it's a
+/// decoder), so this exists solely to let tests verify `imdct` by round-trip.
+#[cfg(test)]
+fn forward_mdct(x: &[f32]) -> Vec<f32> {
+    let n = x.len();
+    let nh = n / 2;
+    let nf = n as f32;
+
+    return (0..nh)
+        .map(|k| {
+            x.iter()
+                .enumerate()
+                .map(|(i, &xi)| {
+                    let angle = (2.0 * PI / nf) * (i as f32 + 0.5 + nf / 4.0) * (k as f32 + 0.5);
+                    xi * angle.cos()
+                })
+                .sum()
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imdct_impulse_is_real_and_finite() {
+        let mdct = Mdct::new(120);
+        let mut freq = vec![0.0f32; 60];
+        freq[0] = 1.0;
+
+        let mut out = vec![0.0f32; 120];
+        mdct.imdct(&freq, &mut out);
+
+        assert!(out.iter().all(|s| s.is_finite()));
+        assert!(out.iter().any(|&s| s.abs() > 1e-6));
+    }
+
+    #[test]
+    fn test_imdct_is_linear() {
+        let n = 240;
+        let mdct = Mdct::new(n);
+
+        let mut a = vec![0.0f32; n / 2];
+        let mut b = vec![0.0f32; n / 2];
+        a[1] = 0.7;
+        b[5] = -1.3;
+
+        let sum: Vec<f32> = a.iter().zip(b.iter()).map(|(x, y)| x + y).collect();
+
+        let mut out_a = vec![0.0f32; n];
+        let mut out_b = vec![0.0f32; n];
+        let mut out_sum = vec![0.0f32; n];
+        mdct.imdct(&a, &mut out_a);
+        mdct.imdct(&b, &mut out_b);
+        mdct.imdct(&sum, &mut out_sum);
+
+        for i in 0..n {
+            assert!((out_sum[i] - (out_a[i] + out_b[i])).abs() < 1e-4);
+        }
+    }
+
+    /// `forward_mdct(imdct(X))` recovers `X` scaled by exactly `n/2`, per the orthogonality of
+    /// the type-IV DCT this transform pair is built from. This is the round-trip check against
+    /// a known-good pair the `imdct`/`forward_mdct` formulas above define: since both are coded
+    /// directly from the textbook definition (not the fast decomposition this module used to
+    /// implement, which a prior version of this test suite never actually validated), agreement
+    /// here is a meaningful correctness check rather than a tautology.
+    #[test]
+    fn test_imdct_round_trip_recovers_scaled_input() {
+        let n = 16;
+        let mdct = Mdct::new(n);
+
+        let freq = vec![0.94, -1.4, -0.68, 0.37, -1.02, -0.07, 0.18, -0.83];
+        let mut time = vec![0.0f32; n];
+        mdct.imdct(&freq, &mut time);
+
+        let recovered = forward_mdct(&time);
+
+        for (k, &coeff) in freq.iter().enumerate() {
+            assert!((recovered[k] - coeff * (n as f32 / 2.0)).abs() < 1e-3, "coefficient {k}: {} vs {}", recovered[k], coeff * (n as f32 / 2.0));
+        }
+    }
+
+    /// A single-impulse input is the simplest case whose exact output is hand-verifiable: with
+    /// only `freq[0]` nonzero, `out[i] = cos((2*pi/n) * (i + 0.5 + n/4) * 0.5)` for every `i`.
+    #[test]
+    fn test_imdct_matches_closed_form_for_dc_input() {
+        let n = 8;
+        let mdct = Mdct::new(n);
+        let mut freq = vec![0.0f32; n / 2];
+        freq[0] = 1.0;
+
+        let mut out = vec![0.0f32; n];
+        mdct.imdct(&freq, &mut out);
+
+        for i in 0..n {
+            let expected = ((2.0 * PI / n as f32) * (i as f32 + 0.5 + n as f32 / 4.0) * 0.5).cos();
+            assert!((out[i] - expected).abs() < 1e-5, "out[{i}]: {} vs {expected}", out[i]);
+        }
+    }
+
+    #[test]
+    fn test_imdct_is_odd_in_its_input() {
+        // freq -> -freq must negate every output sample, since imdct is linear.
+        let n = 32;
+        let mdct = Mdct::new(n);
+        let mut freq = vec![0.0f32; n / 2];
+        freq[0] = 1.0;
+        let mut pos = vec![0.0f32; n];
+        mdct.imdct(&freq, &mut pos);
+
+        freq[0] = -1.0;
+        let mut neg = vec![0.0f32; n];
+        mdct.imdct(&freq, &mut neg);
+
+        for i in 0..n {
+            assert!((pos[i] + neg[i]).abs() < 1e-5);
+        }
+    }
+}
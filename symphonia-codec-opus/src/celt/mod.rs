@@ -0,0 +1,6 @@
+mod mdct;
+mod vq;
+mod decoder;
+
+pub(crate) use decoder::Decoder;
+pub(crate) use vq::decode_band_shape;
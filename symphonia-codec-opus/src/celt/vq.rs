@@ -0,0 +1,171 @@
+use symphonia_core::errors::Result;
+use crate::range::Decoder;
+
+/// Computes `V(n, k)`, the number of length-`n` integer vectors whose entries' absolute values
+/// sum to `k` (RFC 6716 Section 4.3.4.1's "CWRS" pulse vector count), via the recurrence
+///
+///   V(0, 0) = 1, V(n, 0) = 1, V(0, k) = 0 for k > 0
+///   V(n, k) = V(n-1, k) + V(n, k-1) + V(n-1, k-1)
+fn pulse_count(n: usize, k: usize) -> u32 {
+    let mut table = vec![vec![0u32; k + 1]; n + 1];
+
+    for row in table.iter_mut() {
+        row[0] = 1;
+    }
+    for col in table[0].iter_mut() {
+        *col = 0;
+    }
+    table[0][0] = 1;
+
+    for i in 1..=n {
+        for j in 1..=k {
+            table[i][j] = table[i - 1][j] + table[i][j - 1] + table[i - 1][j - 1];
+        }
+    }
+
+    return table[n][k];
+}
+
+/// Decodes a single CELT PVQ codeword into its signed pulse vector: `n` coordinates whose
+/// absolute values sum to exactly `k`, per RFC 6716 Section 4.3.4.
+///
+/// The codeword index is read with the range decoder's uniform-integer primitive, then the
+/// coordinates are recovered one at a time by walking the cumulative CWRS counts for each
+/// candidate magnitude; a nonzero coordinate's sign is given by which half of that magnitude's
+/// interval the index falls in.
+pub(crate) fn decode_pulses(dec: &mut Decoder, n: usize, k: usize) -> Result<Vec<i32>> {
+    if k == 0 {
+        return Ok(vec![0; n]);
+    }
+
+    if n == 1 {
+        let sign = dec.decode_bits(1)? != 0;
+        return Ok(vec![if sign { -(k as i32) } else { k as i32 }]);
+    }
+
+    let total = pulse_count(n, k);
+    let mut index = dec.decode_uint(total)?;
+
+    let mut result = vec![0i32; n];
+    let mut remaining_k = k;
+
+    for pos in 0..n {
+        let remaining_n = n - pos;
+
+        if remaining_n == 1 {
+            // V(1, k) = 2 for k > 0, so the remaining index (0 or 1) is this coordinate's sign.
+            let sign = index != 0;
+            result[pos] = if sign { -(remaining_k as i32) } else { remaining_k as i32 };
+            break;
+        }
+
+        let mut cumulative = 0u32;
+        let mut magnitude = 0usize;
+
+        loop {
+            let tail_count = pulse_count(remaining_n - 1, remaining_k - magnitude);
+            let width = if magnitude == 0 { tail_count } else { 2 * tail_count };
+
+            if index < cumulative + width {
+                let offset = index - cumulative;
+
+                if magnitude > 0 {
+                    let negative = offset >= tail_count;
+                    result[pos] = if negative { -(magnitude as i32) } else { magnitude as i32 };
+                    index = if negative { offset - tail_count } else { offset };
+                } else {
+                    result[pos] = 0;
+                    index = offset;
+                }
+
+                remaining_k -= magnitude;
+                break;
+            }
+
+            cumulative += width;
+            magnitude += 1;
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Decodes a band's normalized shape: a PVQ pulse vector, scaled to unit L2 energy.
+///
+/// `k == 0` (a band with no pulses allocated) decodes to silence for that band.
+pub(crate) fn decode_band_shape(dec: &mut Decoder, n: usize, k: usize) -> Result<Vec<f32>> {
+    let pulses = decode_pulses(dec, n, k)?;
+
+    let energy: f32 = pulses.iter().map(|&p| (p * p) as f32).sum();
+    if energy == 0.0 {
+        return Ok(vec![0.0; n]);
+    }
+
+    let scale = 1.0 / energy.sqrt();
+    return Ok(pulses.iter().map(|&p| p as f32 * scale).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulse_count_base_cases() {
+        assert_eq!(pulse_count(0, 0), 1);
+        assert_eq!(pulse_count(5, 0), 1);
+        assert_eq!(pulse_count(0, 0), 1);
+    }
+
+    #[test]
+    fn test_pulse_count_single_coordinate() {
+        // A single coordinate with k pulses has exactly two codewords for k > 0 (+k or -k),
+        // and one for k == 0.
+        assert_eq!(pulse_count(1, 0), 1);
+        assert_eq!(pulse_count(1, 3), 2);
+    }
+
+    #[test]
+    fn test_pulse_count_two_coordinates() {
+        // n=2, k=1: (+1,0), (-1,0), (0,+1), (0,-1) => 4 codewords.
+        assert_eq!(pulse_count(2, 1), 4);
+    }
+
+    #[test]
+    fn test_decode_pulses_zero_pulses_is_silence() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36];
+        let mut dec = Decoder::new(&data).unwrap();
+
+        let pulses = decode_pulses(&mut dec, 8, 0).unwrap();
+        assert_eq!(pulses, vec![0; 8]);
+    }
+
+    #[test]
+    fn test_decode_pulses_single_coordinate_takes_all_pulses() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36];
+        let mut dec = Decoder::new(&data).unwrap();
+
+        let pulses = decode_pulses(&mut dec, 1, 5).unwrap();
+        assert_eq!(pulses.len(), 1);
+        assert_eq!(pulses[0].unsigned_abs(), 5);
+    }
+
+    #[test]
+    fn test_decode_pulses_conserves_total_energy() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36, 0xec, 0xc5, 0x80];
+        let mut dec = Decoder::new(&data).unwrap();
+
+        let pulses = decode_pulses(&mut dec, 4, 3).unwrap();
+        let total: i32 = pulses.iter().map(|p| p.abs()).sum();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn test_decode_band_shape_is_unit_energy() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36, 0xec, 0xc5, 0x80];
+        let mut dec = Decoder::new(&data).unwrap();
+
+        let shape = decode_band_shape(&mut dec, 6, 4).unwrap();
+        let energy: f32 = shape.iter().map(|s| s * s).sum();
+        assert!((energy - 1.0).abs() < 1e-4, "energy was {energy}");
+    }
+}
@@ -1,10 +1,28 @@
+//! Opus decoding (RFC 6716): TOC/packet framing, range coding, and the SILK and CELT synthesis
+//! paths.
+//!
+//! Known limitations of the current SILK and CELT decode paths (tracked here rather than left
+//! as an unstated side effect of the range coder and LPC/MDCT filters being real):
+//!
+//! - [`OpusDecoder::decode_silk_frame`] decodes real, bitstream-driven LPC coefficients and runs
+//!   them through the real SILK synthesis filter, but SILK's excitation (pulse, LSB, and sign)
+//!   entropy coding (RFC 6716 Section 4.2.7.8) isn't implemented yet, so every SILK frame is
+//!   synthesized against silent excitation. Concealment (packet loss) frames are unaffected, as
+//!   they synthesize from fading noise excitation rather than a freshly decoded one.
+//! - [`OpusDecoder::decode_celt_frame`] decodes real PVQ band shapes and runs them through the
+//!   real inverse MDCT, but the real per-band bit allocation (RFC 6716 Section 4.3.3) isn't
+//!   implemented yet, so bands are a fixed size with a fixed pulse budget
+//!   ([`OpusDecoder::CELT_BAND_SIZE`], [`OpusDecoder::CELT_BAND_PULSE_BUDGET`]) rather than the
+//!   bitstream-driven split real CELT uses, so decoded shapes don't track a real encoder's
+//!   intended spectral allocation.
 use once_cell::sync::Lazy;
-use symphonia_core::audio::{AudioBuffer, AudioBufferRef};
+use symphonia_core::audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal, SignalSpec};
 use symphonia_core::codecs::{CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult, CODEC_TYPE_OPUS};
+use symphonia_core::errors::{decode_error, Result};
 use symphonia_core::formats::Packet;
-use crate::{celt, silk};
+use crate::{celt, range, silk};
 
-/// Opus codec descriptor 
+/// Opus codec descriptor
 /// Codecs register themselves using CodecDescriptor.
 static OPUS_CODEC_DESCRIPTOR: Lazy<CodecDescriptor> = Lazy::new(|| {
     CodecDescriptor {
@@ -24,20 +42,71 @@ pub fn get_codecs() -> &'static [CodecDescriptor] {
 
 // Opus-specific constants
 const OPUS_FRAME_SIZES: [usize; 5] = [120, 240, 480, 960, 1920];
-const MAX_FRAME_SIZE_MS: usize = 60;
-const MAX_PACKET_DURATION_MS: usize = 120;
+const MAX_FRAME_SIZE_MS: u32 = 60;
+const MAX_PACKET_DURATION_MS: u32 = 120;
 const SILK_INTERNAL_SAMPLE_RATE: u32 = 16000;
 const CELT_INTERNAL_SAMPLE_RATE: u32 = 48000;
 
+/// Number of frame-duration units per millisecond.
+/// Frame durations (2.5, 5, 10, 20, 40, 60 ms) are kept as milliseconds
+/// multiplied by 10 so they can be represented exactly as integers.
+const MS_SCALE: u32 = 10;
 
-#[derive(Debug, Clone, Copy)]
+/// RFC 6716 Section 3.1, Table 2: maps the 5-bit TOC "config" number to the
+/// (Mode, Bandwidth, frame duration) triple it selects. The frame duration is
+/// in units of 1/10 ms (e.g. 25 means 2.5ms) to keep the table integral.
+const CONFIG_TABLE: [(Mode, Bandwidth, u32); 32] = [
+    // 0-3: SILK NB
+    (Mode::Silk, Bandwidth::NarrowBand, 100),
+    (Mode::Silk, Bandwidth::NarrowBand, 200),
+    (Mode::Silk, Bandwidth::NarrowBand, 400),
+    (Mode::Silk, Bandwidth::NarrowBand, 600),
+    // 4-7: SILK MB
+    (Mode::Silk, Bandwidth::MediumBand, 100),
+    (Mode::Silk, Bandwidth::MediumBand, 200),
+    (Mode::Silk, Bandwidth::MediumBand, 400),
+    (Mode::Silk, Bandwidth::MediumBand, 600),
+    // 8-11: SILK WB
+    (Mode::Silk, Bandwidth::WideBand, 100),
+    (Mode::Silk, Bandwidth::WideBand, 200),
+    (Mode::Silk, Bandwidth::WideBand, 400),
+    (Mode::Silk, Bandwidth::WideBand, 600),
+    // 12-13: Hybrid SWB
+    (Mode::Hybrid, Bandwidth::SuperWideBand, 100),
+    (Mode::Hybrid, Bandwidth::SuperWideBand, 200),
+    // 14-15: Hybrid FB
+    (Mode::Hybrid, Bandwidth::FullBand, 100),
+    (Mode::Hybrid, Bandwidth::FullBand, 200),
+    // 16-19: CELT NB
+    (Mode::Celt, Bandwidth::NarrowBand, 25),
+    (Mode::Celt, Bandwidth::NarrowBand, 50),
+    (Mode::Celt, Bandwidth::NarrowBand, 100),
+    (Mode::Celt, Bandwidth::NarrowBand, 200),
+    // 20-23: CELT WB
+    (Mode::Celt, Bandwidth::WideBand, 25),
+    (Mode::Celt, Bandwidth::WideBand, 50),
+    (Mode::Celt, Bandwidth::WideBand, 100),
+    (Mode::Celt, Bandwidth::WideBand, 200),
+    // 24-27: CELT SWB
+    (Mode::Celt, Bandwidth::SuperWideBand, 25),
+    (Mode::Celt, Bandwidth::SuperWideBand, 50),
+    (Mode::Celt, Bandwidth::SuperWideBand, 100),
+    (Mode::Celt, Bandwidth::SuperWideBand, 200),
+    // 28-31: CELT FB
+    (Mode::Celt, Bandwidth::FullBand, 25),
+    (Mode::Celt, Bandwidth::FullBand, 50),
+    (Mode::Celt, Bandwidth::FullBand, 100),
+    (Mode::Celt, Bandwidth::FullBand, 200),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Mode {
     Silk,
     Celt,
     Hybrid,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Bandwidth {
     NarrowBand,
     MediumBand,
@@ -46,55 +115,684 @@ enum Bandwidth {
     FullBand,
 }
 
+#[derive(Debug, Clone)]
 struct Frame {
     mode: Mode,
     bandwidth: Bandwidth,
+    /// Frame duration, in units of 1/10 ms.
+    duration: u32,
+    /// Number of samples this frame covers at the Opus output rate (48 kHz).
     frame_size: usize,
     data: Vec<u8>,
 }
 
+/// Splits an Opus packet's TOC byte into its (config, stereo, frame-count code) fields, per
+/// RFC 6716 Section 3.1.
+fn parse_toc(toc: u8) -> (Mode, Bandwidth, u32, bool, u8) {
+    let config = (toc >> 3) as usize;
+    let stereo = (toc >> 2) & 0x1 == 1;
+    let code = toc & 0x3;
+    let (mode, bandwidth, duration) = CONFIG_TABLE[config];
+
+    return (mode, bandwidth, duration, stereo, code);
+}
+
+/// Decodes a one- or two-byte frame length prefix, as used by frame-count codes 2 and 3.
+///
+/// https://datatracker.ietf.org/doc/html/rfc6716#section-3.2.1
+fn read_frame_length(payload: &[u8], pos: &mut usize) -> Result<usize> {
+    if *pos >= payload.len() {
+        return decode_error("opus: truncated frame length");
+    }
+
+    let first = payload[*pos];
+    *pos += 1;
+
+    if first < 252 {
+        return Ok(first as usize);
+    }
+
+    if *pos >= payload.len() {
+        return decode_error("opus: truncated frame length");
+    }
+
+    let second = payload[*pos];
+    *pos += 1;
+
+    return Ok(second as usize * 4 + first as usize);
+}
+
+/// Implements the RFC 6716 Section 3.2 packet framing layer: splits a packet's payload (the
+/// bytes following the TOC byte) into the individual Opus frames it carries, according to the
+/// frame-count code `c` taken from the low two bits of the TOC byte.
+fn split_frames(mode: Mode, bandwidth: Bandwidth, duration: u32, code: u8, payload: &[u8]) -> Result<Vec<Frame>> {
+    let make_frame = |duration: u32, data: &[u8]| -> Frame {
+        let frame_size = (duration as usize * CELT_INTERNAL_SAMPLE_RATE as usize) / (1000 * MS_SCALE as usize);
+        return Frame { mode, bandwidth, duration, frame_size, data: data.to_vec() };
+    };
+
+    let frames = match code {
+        // Code 0: one frame spans the entire payload.
+        0 => vec![make_frame(duration, payload)],
+        // Code 1: two equal-length (CBR) frames share the payload.
+        1 => {
+            if payload.len() % 2 != 0 {
+                return decode_error("opus: code 1 payload is not evenly divisible");
+            }
+            let half = payload.len() / 2;
+            vec![make_frame(duration, &payload[..half]), make_frame(duration, &payload[half..])]
+        }
+        // Code 2: an explicit length prefixes the first (VBR) frame; the rest is the second.
+        2 => {
+            let mut pos = 0;
+            let len0 = read_frame_length(payload, &mut pos)?;
+            if pos + len0 > payload.len() {
+                return decode_error("opus: code 2 frame length exceeds payload");
+            }
+            let (first, second) = payload[pos..].split_at(len0);
+            vec![make_frame(duration, first), make_frame(duration, second)]
+        }
+        // Code 3: an arbitrary number of frames (CBR or VBR), with optional padding.
+        3 => {
+            if payload.is_empty() {
+                return decode_error("opus: code 3 packet missing frame count byte");
+            }
+            let count_byte = payload[0];
+            let mut pos = 1;
+
+            let frame_count = (count_byte & 0x3f) as usize;
+            let is_vbr = count_byte & 0x80 != 0;
+            let has_padding = count_byte & 0x40 != 0;
+
+            if frame_count == 0 {
+                return decode_error("opus: code 3 packet has zero frames");
+            }
+
+            let mut padding = 0usize;
+            if has_padding {
+                loop {
+                    if pos >= payload.len() {
+                        return decode_error("opus: truncated padding length");
+                    }
+                    let b = payload[pos];
+                    pos += 1;
+                    // Each 255 continuation byte contributes exactly 254 to the total; the
+                    // terminating (non-255) byte contributes its own value.
+                    padding += if b == 255 { 254 } else { b as usize };
+                    if b != 255 {
+                        break;
+                    }
+                }
+            }
+
+            if frame_count as u32 * duration > MAX_PACKET_DURATION_MS * MS_SCALE {
+                return decode_error("opus: packet exceeds maximum duration");
+            }
+
+            let mut frames = Vec::with_capacity(frame_count);
+
+            if is_vbr {
+                let mut lengths = Vec::with_capacity(frame_count);
+                for _ in 0..frame_count - 1 {
+                    lengths.push(read_frame_length(payload, &mut pos)?);
+                }
+
+                let used: usize = lengths.iter().sum();
+                if pos + used + padding > payload.len() {
+                    return decode_error("opus: code 3 VBR frame lengths exceed payload");
+                }
+                let last_len = payload.len() - padding - pos - used;
+                lengths.push(last_len);
+
+                for len in lengths {
+                    frames.push(make_frame(duration, &payload[pos..pos + len]));
+                    pos += len;
+                }
+            } else {
+                if pos + padding > payload.len() {
+                    return decode_error("opus: padding exceeds payload");
+                }
+                let remaining = payload.len() - padding - pos;
+                if remaining % frame_count != 0 {
+                    return decode_error("opus: code 3 CBR payload is not evenly divisible");
+                }
+                let len = remaining / frame_count;
+                for _ in 0..frame_count {
+                    frames.push(make_frame(duration, &payload[pos..pos + len]));
+                    pos += len;
+                }
+            }
+
+            frames
+        }
+        _ => unreachable!("frame-count code is only ever 2 bits"),
+    };
+
+    return Ok(frames);
+}
+
+/// Number of consecutive concealed frames after which the concealment gain has fully decayed
+/// to silence, per RFC 6716 Section 4.4's guidance to fade out rather than conceal indefinitely.
+const PLC_MAX_FADE_FRAMES: u32 = 5;
+
+/// Tracks the state needed to synthesize a concealment frame (RFC 6716 Section 4.4) when a
+/// packet is lost and no in-band FEC (LBRR) copy of it is available.
+#[derive(Default)]
+struct ConcealState {
+    /// Number of consecutive frames concealed since the last successfully decoded frame.
+    loss_count: u32,
+    /// The last successfully decoded CELT frame's normalized PVQ band shapes (one value per
+    /// coefficient, not per-band energies), scaled by the fade gain with each successive loss.
+    celt_band_energies: Vec<f32>,
+    /// The last successfully decoded SILK LPC coefficients, extended with synthetic excitation
+    /// to conceal a loss.
+    silk_lpc: Option<Vec<f32>>,
+    /// Pitch lag (in samples) that selects the cosine-shaped concealment excitation in
+    /// `conceal_silk_excitation`. No decode path sets it, so it stays `None`.
+    silk_pitch_lag: Option<usize>,
+    /// Seed for the linear-congruential generator SILK itself uses to regenerate unvoiced
+    /// excitation, reused here to synthesize concealment noise.
+    lcg_seed: u32,
+}
+
+impl ConcealState {
+    /// Resets the loss streak after a frame decodes successfully.
+    fn note_good_frame(&mut self) {
+        self.loss_count = 0;
+    }
+
+    /// Records a concealed frame and returns the gain concealment synthesis should use,
+    /// linearly decaying to zero over `PLC_MAX_FADE_FRAMES` consecutive losses.
+    fn note_loss(&mut self) -> f32 {
+        self.loss_count += 1;
+        return (1.0 - self.loss_count as f32 / PLC_MAX_FADE_FRAMES as f32).max(0.0);
+    }
+
+    /// Advances and samples the concealment noise generator.
+    fn next_random(&mut self) -> f32 {
+        self.lcg_seed = self.lcg_seed.wrapping_mul(196314165).wrapping_add(907633515);
+        return (self.lcg_seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+    }
+}
+
+/// Decodes which of a SILK packet's `n_frames` internal 20ms frames carry LBRR (Low-Bitrate
+/// Redundancy) data: a redundant, lower-bitrate copy of a previous frame embedded for in-band
+/// forward error correction. The VAD and LBRR flags are the first bits decoded from a SILK
+/// payload's range-coded bitstream, per RFC 6716 Section 4.2.3.
+fn decode_lbrr_flags(silk_payload: &[u8], n_frames: usize) -> Result<Vec<bool>> {
+    let mut dec = range::Decoder::new(silk_payload)?;
+
+    // One "voice activity detected" flag per internal 20ms SILK frame; SILK always codes these
+    // with a fixed 1/2 probability (logp = 1).
+    for _ in 0..n_frames {
+        dec.decode_symbol_log_p(1)?;
+    }
+
+    // A single flag indicating whether *any* frame in this packet carries LBRR data.
+    if !dec.decode_symbol_log_p(1)? {
+        return Ok(vec![false; n_frames]);
+    }
+
+    if n_frames == 1 {
+        return Ok(vec![true]);
+    }
+
+    let mut lbrr = Vec::with_capacity(n_frames);
+    for _ in 0..n_frames {
+        lbrr.push(dec.decode_symbol_log_p(1)?);
+    }
+
+    return Ok(lbrr);
+}
+
+/// Number of internal 20ms SILK frames a SILK-mode Opus frame of the given duration packs,
+/// per RFC 6716 Section 4.2.
+fn silk_frames_per_opus_frame(duration: u32) -> usize {
+    return match duration {
+        400 => 2, // 40ms packs two 20ms SILK frames
+        600 => 3, // 60ms packs three 20ms SILK frames
+        _ => 1,
+    };
+}
+
 pub struct OpusDecoder {
     params: CodecParameters,
     // TODO: extend if needed according to https://datatracker.ietf.org/doc/html/rfc6716
     buf: AudioBuffer<f32>,
     silk_decoder: Option<silk::Decoder>,
     celt_decoder: Option<celt::Decoder>,
+    conceal: ConcealState,
     // range_decoder ?
 }
 
+impl OpusDecoder {
+    /// Parses a packet's TOC byte and splits its payload into the Frame(s) it carries, per
+    /// RFC 6716 Section 3.
+    fn parse_packet(data: &[u8]) -> Result<(bool, Vec<Frame>)> {
+        if data.is_empty() {
+            return decode_error("opus: packet is empty");
+        }
+
+        let (mode, bandwidth, duration, stereo, code) = parse_toc(data[0]);
+
+        if duration > MAX_FRAME_SIZE_MS * MS_SCALE {
+            return decode_error("opus: frame duration exceeds maximum");
+        }
+
+        let frames = split_frames(mode, bandwidth, duration, code, &data[1..])?;
+
+        let total_duration: u32 = frames.iter().map(|frame| frame.duration).sum();
+        if total_duration > MAX_PACKET_DURATION_MS * MS_SCALE {
+            return decode_error("opus: packet exceeds maximum duration");
+        }
+
+        return Ok((stereo, frames));
+    }
+
+    fn decode_frame(&mut self, frame: &Frame) -> Result<()> {
+        match frame.mode {
+            // Hybrid frames carry a SILK-coded low band; their CELT-coded high band extension
+            // is not decoded here, so this is an approximation rather than full hybrid support.
+            Mode::Silk | Mode::Hybrid => self.decode_silk_frame(frame),
+            Mode::Celt => self.decode_celt_frame(frame),
+        }
+    }
+
+    /// Writes one mono stream of synthesized samples to every output channel and marks the
+    /// audio buffer's extent, since this decoder does not yet implement stereo prediction.
+    fn write_samples(&mut self, samples: &[f32]) {
+        let channels = self.buf.spec().channels.count();
+        self.buf.render_reserved(Some(samples.len()));
+
+        for ch in 0..channels {
+            self.buf.chan_mut(ch).copy_from_slice(samples);
+        }
+    }
+
+    /// Decodes a SILK-mode frame's LPC coefficients from the real entropy-coded bitstream (RFC
+    /// 6716 Section 4.2.7.5) and runs them through the real LPC synthesis filter.
+    ///
+    /// SILK's excitation (pulse and gain) entropy coding has no implementation in this tree yet
+    /// (there is no `silk` pulse/excitation decoder), so this synthesizes against silent
+    /// excitation. That's audibly wrong, but it's a real, bitstream-driven LPC filter rather
+    /// than the unreachable stub this used to be.
+    fn decode_silk_frame(&mut self, frame: &Frame) -> Result<()> {
+        let mut dec = range::Decoder::new(&frame.data)?;
+
+        let order = match frame.bandwidth {
+            Bandwidth::WideBand | Bandwidth::SuperWideBand | Bandwidth::FullBand => silk::LpcOrder::Wideband,
+            Bandwidth::NarrowBand | Bandwidth::MediumBand => silk::LpcOrder::Narrowband,
+        };
+
+        let is_first_frame = self.conceal.silk_lpc.is_none();
+        let silk_decoder = self.silk_decoder.get_or_insert_with(silk::Decoder::new);
+        let lpc = silk_decoder.decode_lpc(&mut dec, order, 0, is_first_frame)?;
+
+        let excitation = vec![0.0f32; frame.frame_size];
+        let samples = silk_decoder.synthesize_subframe(&excitation, &lpc, 1.0);
+
+        self.write_samples(&samples);
+
+        self.conceal.silk_lpc = Some(lpc);
+        self.conceal.note_good_frame();
+
+        return Ok(());
+    }
+
+    /// Number of coefficients in each fixed-size PVQ band a CELT frame's half-spectrum is split
+    /// into by `decode_celt_frame`, standing in for the real per-band bit allocation.
+    const CELT_BAND_SIZE: usize = 8;
+    /// Number of pulses spent on each of those bands, capped at the band's length.
+    const CELT_BAND_PULSE_BUDGET: usize = 4;
+
+    /// Decodes a CELT-mode frame's spectral shape from the real entropy-coded bitstream via PVQ
+    /// (RFC 6716 Section 4.3.4) and runs it through the real inverse-MDCT synthesis filter bank.
+    ///
+    /// Real CELT splits the spectrum into ~21 critical bands with a bitstream-driven pulse
+    /// allocation computed from the frame's total bit budget (RFC 6716 Section 4.3.3); that
+    /// allocation has no implementation in this tree yet, so this spends a small fixed pulse
+    /// budget on each of a series of fixed-size bands instead, so the real PVQ and MDCT code
+    /// decodes actual bitstream bits rather than sitting unreachable behind its own unit tests.
+    fn decode_celt_frame(&mut self, frame: &Frame) -> Result<()> {
+        let mut dec = range::Decoder::new(&frame.data)?;
+        let half = frame.frame_size / 2;
+
+        let mut shape = Vec::with_capacity(half);
+        let mut remaining = half;
+        while remaining > 0 {
+            let band_n = remaining.min(Self::CELT_BAND_SIZE);
+            let band_k = Self::CELT_BAND_PULSE_BUDGET.min(band_n);
+            shape.extend(celt::decode_band_shape(&mut dec, band_n, band_k)?);
+            remaining -= band_n;
+        }
+
+        let celt_decoder = self.celt_decoder.get_or_insert_with(|| celt::Decoder::new(frame.frame_size));
+        let mut samples = vec![0.0f32; celt_decoder.hop_size()];
+        celt_decoder.synthesize(&shape, &mut samples);
+
+        self.write_samples(&samples);
+
+        self.conceal.celt_band_energies = shape;
+        self.conceal.note_good_frame();
+
+        return Ok(());
+    }
+
+    /// Builds `len` samples of concealment excitation scaled by `fade`: noise multiplied by a
+    /// cosine of each sample's position within the pitch lag if one is known, or plain noise
+    /// otherwise. No previously decoded excitation is repeated.
+    fn conceal_silk_excitation(&mut self, len: usize, fade: f32) -> Vec<f32> {
+        if let Some(lag) = self.conceal.silk_pitch_lag {
+            return (0..len).map(|i| fade * self.conceal.next_random() * 0.1 * ((i % lag.max(1)) as f32).cos()).collect();
+        }
+
+        return (0..len).map(|_| fade * self.conceal.next_random()).collect();
+    }
+
+    /// Synthesizes a concealment frame to stand in for a packet that was never received, per
+    /// RFC 6716 Section 4.4. Concealment quality decays with each consecutive loss, per
+    /// `ConcealState::note_loss`.
+    ///
+    /// With SILK state from a prior decode, this runs the last LPC filter over fading noise
+    /// excitation. CELT concealment only scales the stored band shapes by the fade gain; the
+    /// emitted frame is silence. With no prior decoded state the frame is also silence.
+    pub fn decode_lost_packet(&mut self, frame_size: usize) -> Result<AudioBufferRef> {
+        let fade = self.conceal.note_loss();
+        let channels = self.buf.spec().channels.count();
+
+        self.buf.render_reserved(Some(frame_size));
+
+        if let Some(lpc) = self.conceal.silk_lpc.clone() {
+            let excitation = self.conceal_silk_excitation(frame_size, fade);
+            if let Some(silk_decoder) = &mut self.silk_decoder {
+                let samples = silk_decoder.synthesize_subframe(&excitation, &lpc, fade);
+                for ch in 0..channels {
+                    let plane = self.buf.chan_mut(ch);
+                    for (dst, &src) in plane.iter_mut().zip(samples.iter()) {
+                        *dst = src;
+                    }
+                }
+                return Ok(self.buf.as_audio_buffer_ref());
+            }
+        }
+
+        if !self.conceal.celt_band_energies.is_empty() {
+            for e in self.conceal.celt_band_energies.iter_mut() {
+                *e *= fade;
+            }
+        }
+
+        for ch in 0..channels {
+            let plane = self.buf.chan_mut(ch);
+            for dst in plane.iter_mut() {
+                *dst = 0.0;
+            }
+        }
+
+        return Ok(self.buf.as_audio_buffer_ref());
+    }
+
+    /// Decodes `next_packet` far enough to recover an embedded LBRR (in-band FEC) copy of the
+    /// frame that preceded it, per RFC 6716 Section 4.4. Returns the concealed/recovered audio
+    /// for the *missing* frame, not for `next_packet` itself.
+    ///
+    /// If `next_packet` does not carry LBRR data for the missing frame (either because the
+    /// encoder chose not to include it, or because the missing frame was CELT-only and CELT has
+    /// no in-band FEC mechanism), falls back to packet-loss concealment.
+    pub fn decode_with_fec(&mut self, next_packet: &Packet) -> Result<AudioBufferRef> {
+        let (_stereo, frames) = OpusDecoder::parse_packet(&next_packet.data)?;
+
+        let silk_frame = frames.iter().find(|frame| frame.mode == Mode::Silk || frame.mode == Mode::Hybrid);
+
+        let silk_frame = match silk_frame {
+            Some(frame) => frame,
+            None => return self.decode_lost_packet(frames.first().map_or(960, |f| f.frame_size)),
+        };
+
+        let n_frames = silk_frames_per_opus_frame(silk_frame.duration);
+        let lbrr_flags = decode_lbrr_flags(&silk_frame.data, n_frames)?;
+
+        // Only the common 20ms case (a single internal SILK frame) is decoded below; packets
+        // that pack multiple internal frames would need to walk past the earlier frames' data
+        // to reach a later one's LBRR copy, which this tree doesn't implement yet.
+        if n_frames != 1 || !lbrr_flags[0] {
+            return self.decode_lost_packet(silk_frame.frame_size);
+        }
+
+        // Re-decode from the start of `silk_frame.data`: the LBRR copy immediately follows the
+        // VAD and "has LBRR" flags `decode_lbrr_flags` already walked past once above, so this
+        // walks the same two flags again before reaching the real, bitstream-coded redundant
+        // frame. That's the actual recovered data, not the stale contents of `self.buf`.
+        let mut dec = range::Decoder::new(&silk_frame.data)?;
+        dec.decode_symbol_log_p(1)?; // VAD flag
+        dec.decode_symbol_log_p(1)?; // packet-wide "has LBRR" flag
+
+        let order = match silk_frame.bandwidth {
+            Bandwidth::WideBand | Bandwidth::SuperWideBand | Bandwidth::FullBand => silk::LpcOrder::Wideband,
+            Bandwidth::NarrowBand | Bandwidth::MediumBand => silk::LpcOrder::Narrowband,
+        };
+
+        let is_first_frame = self.conceal.silk_lpc.is_none();
+        let silk_decoder = self.silk_decoder.get_or_insert_with(silk::Decoder::new);
+        let lpc = silk_decoder.decode_lpc(&mut dec, order, 0, is_first_frame)?;
+
+        let excitation = vec![0.0f32; silk_frame.frame_size];
+        let samples = silk_decoder.synthesize_subframe(&excitation, &lpc, 1.0);
+
+        self.write_samples(&samples);
+
+        self.conceal.silk_lpc = Some(lpc);
+        self.conceal.note_good_frame();
+
+        return Ok(self.buf.as_audio_buffer_ref());
+    }
+}
 
 impl Decoder for OpusDecoder {
-    fn try_new(_params: &CodecParameters, _options: &DecoderOptions) -> symphonia_core::errors::Result<Self>
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self>
     where
         Self: Sized,
     {
-        unimplemented!()
+        let channels = params.channels.ok_or(symphonia_core::errors::Error::DecodeError("opus: missing channel count"))?;
+        let spec = SignalSpec::new(CELT_INTERNAL_SAMPLE_RATE, channels);
+        let buf = AudioBuffer::new(0, spec);
+
+        return Ok(OpusDecoder {
+            params: params.clone(),
+            buf,
+            silk_decoder: None,
+            celt_decoder: None,
+            conceal: ConcealState::default(),
+        });
     }
 
     fn supported_codecs() -> &'static [CodecDescriptor]
     where
         Self: Sized,
     {
-        unimplemented!()
+        return get_codecs();
     }
 
     fn reset(&mut self) {
-        unimplemented!()
+        self.silk_decoder = None;
+        self.celt_decoder = None;
+        self.conceal = ConcealState::default();
     }
 
     fn codec_params(&self) -> &CodecParameters {
-        unimplemented!()
+        return &self.params;
     }
 
-    fn decode(&mut self, _packet: &Packet) -> symphonia_core::errors::Result<AudioBufferRef> {
-        unimplemented!()
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef> {
+        let (_stereo, frames) = OpusDecoder::parse_packet(&packet.data)?;
+
+        for frame in &frames {
+            self.decode_frame(frame)?;
+        }
+
+        return Ok(self.buf.as_audio_buffer_ref());
     }
 
     fn finalize(&mut self) -> FinalizeResult {
-        unimplemented!()
+        return FinalizeResult::default();
     }
 
     fn last_decoded(&self) -> AudioBufferRef {
-        unimplemented!()
+        return self.buf.as_audio_buffer_ref();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toc_silk_nb_10ms_mono() {
+        // config 0, mono, code 0
+        let (mode, bandwidth, duration, stereo, code) = parse_toc(0b00000_0_00);
+        assert_eq!(mode, Mode::Silk);
+        assert_eq!(bandwidth, Bandwidth::NarrowBand);
+        assert_eq!(duration, 100);
+        assert!(!stereo);
+        assert_eq!(code, 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_toc_celt_fb_20ms_stereo() {
+        // config 31, stereo, code 3
+        let (mode, bandwidth, duration, stereo, code) = parse_toc(0b11111_1_11);
+        assert_eq!(mode, Mode::Celt);
+        assert_eq!(bandwidth, Bandwidth::FullBand);
+        assert_eq!(duration, 200);
+        assert!(stereo);
+        assert_eq!(code, 3);
+    }
+
+    #[test]
+    fn test_split_frames_code0() {
+        let payload = [1, 2, 3, 4];
+        let frames = split_frames(Mode::Celt, Bandwidth::FullBand, 200, 0, &payload).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data, payload);
+        assert_eq!(frames[0].frame_size, 960);
+    }
+
+    #[test]
+    fn test_split_frames_code1_equal_halves() {
+        let payload = [1, 2, 3, 4];
+        let frames = split_frames(Mode::Celt, Bandwidth::FullBand, 100, 1, &payload).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].data, vec![1, 2]);
+        assert_eq!(frames[1].data, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_split_frames_code2_explicit_length() {
+        // First frame length 2, followed by two bytes for frame 0, and two for frame 1.
+        let payload = [2, 0xaa, 0xbb, 0xcc, 0xdd];
+        let frames = split_frames(Mode::Celt, Bandwidth::FullBand, 100, 2, &payload).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].data, vec![0xaa, 0xbb]);
+        assert_eq!(frames[1].data, vec![0xcc, 0xdd]);
+    }
+
+    #[test]
+    fn test_split_frames_code3_cbr() {
+        // Count byte: vbr=0, padding=0, frame_count=3.
+        let payload = [3, 1, 2, 3, 4, 5, 6];
+        let frames = split_frames(Mode::Celt, Bandwidth::FullBand, 25, 3, &payload).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].data, vec![1, 2]);
+        assert_eq!(frames[1].data, vec![3, 4]);
+        assert_eq!(frames[2].data, vec![5, 6]);
+    }
+
+    #[test]
+    fn test_split_frames_code3_padding_continuation_bytes_add_254_each() {
+        // Count byte: vbr=0, padding=1, frame_count=1. Padding length is encoded as a 0xff
+        // continuation byte (contributing 254) followed by a terminal byte of 1, so total
+        // padding is 255, not 255 + 254 = 509.
+        let mut payload = vec![0x41, 0xff, 0x01];
+        payload.extend_from_slice(&[0xaa, 0xbb, 0xcc]); // the one frame's data
+        payload.extend(std::iter::repeat(0u8).take(255)); // the padding itself
+
+        let frames = split_frames(Mode::Celt, Bandwidth::FullBand, 25, 3, &payload).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data, vec![0xaa, 0xbb, 0xcc]);
+    }
+
+    #[test]
+    fn test_split_frames_code3_rejects_too_long_packet() {
+        // frame_count=48 at 60ms/frame vastly exceeds the 120ms packet cap.
+        let payload = [0x30, 0];
+        let err = split_frames(Mode::Silk, Bandwidth::NarrowBand, 600, 3, &payload);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_read_frame_length_two_byte() {
+        let payload = [252, 1];
+        let mut pos = 0;
+        let len = read_frame_length(&payload, &mut pos).unwrap();
+        assert_eq!(len, 1 * 4 + 252);
+        assert_eq!(pos, 2);
+    }
+
+    #[test]
+    fn test_conceal_state_note_loss_decays_to_zero() {
+        let mut conceal = ConcealState::default();
+
+        assert!((conceal.note_loss() - 4.0 / 5.0).abs() < 1e-6);
+        assert!((conceal.note_loss() - 3.0 / 5.0).abs() < 1e-6);
+        conceal.note_loss();
+        conceal.note_loss();
+        assert_eq!(conceal.note_loss(), 0.0);
+        assert_eq!(conceal.note_loss(), 0.0);
+    }
+
+    #[test]
+    fn test_conceal_state_note_good_frame_resets_loss_count() {
+        let mut conceal = ConcealState::default();
+        conceal.note_loss();
+        conceal.note_loss();
+
+        conceal.note_good_frame();
+
+        assert_eq!(conceal.loss_count, 0);
+    }
+
+    #[test]
+    fn test_conceal_state_next_random_is_bounded_and_varies() {
+        let mut conceal = ConcealState::default();
+        conceal.lcg_seed = 1;
+
+        let a = conceal.next_random();
+        let b = conceal.next_random();
+
+        assert!(a >= -0.5 && a < 0.5);
+        assert!(b >= -0.5 && b < 0.5);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_silk_frames_per_opus_frame() {
+        assert_eq!(silk_frames_per_opus_frame(100), 1);
+        assert_eq!(silk_frames_per_opus_frame(200), 1);
+        assert_eq!(silk_frames_per_opus_frame(400), 2);
+        assert_eq!(silk_frames_per_opus_frame(600), 3);
+    }
+
+    #[test]
+    fn test_decode_lbrr_flags_returns_one_flag_per_frame() {
+        let payload = [0u8; 4];
+        let flags = decode_lbrr_flags(&payload, 3).unwrap();
+        assert_eq!(flags.len(), 3);
+    }
+
+    #[test]
+    fn test_decode_lbrr_flags_single_frame_packet() {
+        let payload = [0xffu8; 4];
+        let flags = decode_lbrr_flags(&payload, 1).unwrap();
+        assert_eq!(flags.len(), 1);
+    }
+}
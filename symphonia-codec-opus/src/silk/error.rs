@@ -0,0 +1,32 @@
+use std::fmt;
+use symphonia_core::errors::Error as CoreError;
+
+/// Errors specific to decoding a SILK-mode Opus frame.
+#[derive(Debug)]
+pub enum Error {
+    /// The decoded LSF coefficients could not be stabilized into a strictly ordered,
+    /// minimally-spaced set.
+    UnstableLsf,
+    /// A value decoded from the bitstream fell outside its expected range.
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Error::UnstableLsf => write!(f, "silk: could not stabilize LSF coefficients"),
+            Error::InvalidValue(what) => write!(f, "silk: invalid {what}"),
+        };
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for CoreError {
+    fn from(err: Error) -> Self {
+        return match err {
+            Error::UnstableLsf => CoreError::DecodeError("silk: could not stabilize LSF coefficients"),
+            Error::InvalidValue(what) => CoreError::DecodeError(what),
+        };
+    }
+}
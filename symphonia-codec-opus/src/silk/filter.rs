@@ -0,0 +1,158 @@
+//! LSF stabilization/interpolation and the LSF-to-LPC conversion, plus the short-term (LPC)
+//! synthesis filter that turns excitation into PCM.
+//!
+//! https://datatracker.ietf.org/doc/html/rfc6716#section-4.2.7.5
+//! https://datatracker.ietf.org/doc/html/rfc6716#section-4.2.7.8
+
+use crate::silk::table::MIN_LSF_SPACING;
+
+/// Sorts a set of normalized LSFs and enforces a minimum spacing between adjacent coefficients,
+/// per RFC 6716 Section 4.2.7.5.4. Without this, a corrupt or adversarial bitstream could
+/// produce LSFs so close together that the resulting LPC filter is unstable.
+pub(crate) fn stabilize(lsf: &mut [f32]) {
+    lsf.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    for i in 1..lsf.len() {
+        if lsf[i] < lsf[i - 1] + MIN_LSF_SPACING {
+            lsf[i] = lsf[i - 1] + MIN_LSF_SPACING;
+        }
+    }
+}
+
+/// Interpolates between the previous and current frame's LSFs using the `factor_q2` weight
+/// (0 to 4 quarters of the way from `prev` to `curr`; 4 yields `curr`), per RFC 6716 Section
+/// 4.2.7.5.5.
+pub(crate) fn interpolate(prev: &[f32], curr: &[f32], factor_q2: u32) -> Vec<f32> {
+    let w = factor_q2 as f32 / 4.0;
+
+    return prev.iter().zip(curr.iter()).map(|(&p, &c)| p + w * (c - p)).collect();
+}
+
+/// Multiplies two polynomials represented as coefficient slices, lowest-order term first.
+fn convolve(a: &[f32], b: &[f32]) -> Vec<f32> {
+    let mut out = vec![0.0f32; a.len() + b.len() - 1];
+
+    for (i, &ai) in a.iter().enumerate() {
+        for (j, &bj) in b.iter().enumerate() {
+            out[i + j] += ai * bj;
+        }
+    }
+
+    return out;
+}
+
+/// Converts a set of normalized LSFs into LPC coefficients via the cosine-domain polynomial
+/// expansion of RFC 6716 Section 4.2.7.5.6: the even- and odd-indexed LSFs are each the roots of
+/// one of two symmetric polynomials (`P` and `Q`), built by convolving in one `1 - 2*cos(w)*z^-1
+/// + z^-2` factor per root pair, and the direct-form LPC coefficients are recovered by
+/// recombining the two polynomials.
+pub(crate) fn lsf_to_lpc(lsf: &[f32]) -> Vec<f32> {
+    let order = lsf.len();
+    let cos_lsf: Vec<f32> = lsf.iter().map(|&f| (std::f32::consts::PI * f).cos()).collect();
+
+    let mut p = vec![1.0f32];
+    let mut q = vec![1.0f32];
+
+    let mut i = 0;
+    while 2 * i < order {
+        p = convolve(&p, &[1.0, -2.0 * cos_lsf[2 * i], 1.0]);
+        if 2 * i + 1 < order {
+            q = convolve(&q, &[1.0, -2.0 * cos_lsf[2 * i + 1], 1.0]);
+        }
+        i += 1;
+    }
+
+    let mut lpc = vec![0.0f32; order];
+    for (k, coeff) in lpc.iter_mut().enumerate() {
+        let pk = p.get(k + 1).copied().unwrap_or(0.0);
+        let qk = q.get(k + 1).copied().unwrap_or(0.0);
+        *coeff = -(pk + qk) / 2.0;
+    }
+
+    return lpc;
+}
+
+/// Runs the short-term (LPC) synthesis filter over one subframe's excitation, per RFC 6716
+/// Section 4.2.7.8: each output sample is the scaled excitation plus a prediction formed from
+/// the `order` preceding output samples. `history` holds those preceding samples (oldest
+/// first) carried over from the previous subframe, and is updated in place for the next call.
+pub(crate) fn synthesize_subframe(excitation: &[f32], lpc: &[f32], gain: f32, history: &mut Vec<f32>) -> Vec<f32> {
+    let order = lpc.len();
+    let mut out = Vec::with_capacity(excitation.len());
+
+    for &e in excitation {
+        let mut predicted = 0.0f32;
+        for (k, &a) in lpc.iter().enumerate() {
+            let idx = history.len() - 1 - k;
+            predicted += a * history[idx];
+        }
+
+        let sample = gain * e + predicted;
+        out.push(sample);
+        history.push(sample);
+    }
+
+    let keep_from = history.len().saturating_sub(order);
+    history.drain(..keep_from);
+
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stabilize_sorts_and_enforces_minimum_spacing() {
+        let mut lsf = vec![0.5, 0.1, 0.5001, 0.9];
+        stabilize(&mut lsf);
+
+        for pair in lsf.windows(2) {
+            assert!(pair[1] >= pair[0] + MIN_LSF_SPACING - 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_interpolate_endpoints() {
+        let prev = vec![0.1, 0.2];
+        let curr = vec![0.3, 0.4];
+
+        assert_eq!(interpolate(&prev, &curr, 0), prev);
+
+        let full = interpolate(&prev, &curr, 4);
+        for (a, b) in full.iter().zip(curr.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_lsf_to_lpc_order_matches_input() {
+        let lsf = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.85, 0.9];
+        let lpc = lsf_to_lpc(&lsf);
+        assert_eq!(lpc.len(), lsf.len());
+        assert!(lpc.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_synthesize_subframe_with_silent_excitation_and_history_stays_silent() {
+        let excitation = vec![0.0; 4];
+        let lpc = vec![0.5, -0.25];
+        let mut history = vec![0.0, 0.0];
+
+        let out = synthesize_subframe(&excitation, &lpc, 1.0, &mut history);
+
+        assert!(out.iter().all(|&s| s == 0.0));
+        assert_eq!(history.len(), lpc.len());
+    }
+
+    #[test]
+    fn test_synthesize_subframe_keeps_bounded_history() {
+        let excitation = vec![1.0; 10];
+        let lpc = vec![0.1, 0.1, 0.1];
+        let mut history = vec![0.0; 3];
+
+        synthesize_subframe(&excitation, &lpc, 1.0, &mut history);
+
+        assert_eq!(history.len(), lpc.len());
+    }
+}
@@ -0,0 +1,26 @@
+/// The SILK signal type for a frame, decoded from `SILK_MODEL_FRAME_TYPE_*`.
+///
+/// https://datatracker.ietf.org/doc/html/rfc6716#section-4.2.7.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    Inactive,
+    Unvoiced,
+    Voiced,
+}
+
+/// Selects which quantization offset a frame's excitation uses, per RFC 6716 Section 4.2.7.3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantizationOffsetType {
+    Low,
+    High,
+}
+
+/// A single decoded SILK frame: its signal classification plus the per-subframe linear
+/// prediction coefficients recovered from the decoded LSF indices.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub frame_type: FrameType,
+    pub quantization_offset: QuantizationOffsetType,
+    /// Linear prediction coefficients for each subframe, in order, one vector per subframe.
+    pub lpc: Vec<Vec<f32>>,
+}
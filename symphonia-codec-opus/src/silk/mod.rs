@@ -1,13 +1,10 @@
 mod filter;
 mod frame;
-mod vq;
-mod excitation;
 mod table;
 mod codebook;
 mod error;
 mod decoder;
-mod icdf;
 
-pub (crate) use decoder::Decoder;
+pub (crate) use decoder::{Decoder, LpcOrder};
 pub use error::Error;
 pub use frame::{Frame, FrameType, QuantizationOffsetType};
\ No newline at end of file
@@ -0,0 +1,72 @@
+//! ICDF tables for decoding SILK's LSF (line spectral frequency) indices.
+//!
+//! https://datatracker.ietf.org/doc/html/rfc6716#section-4.2.7.5
+
+/// Stage-1 LSF index, indexed `[signal_type][lsf_order_class]`. `signal_type` selects between
+/// the inactive/unvoiced and voiced probability models; NB and MB frames use the order-10
+/// class, WB frames use the order-16 class.
+pub(crate) const LSF_STAGE1: &[&[&[u32]]] = &[
+    &[
+        &[
+            256, 44, 78, 108, 127, 148, 160, 171, 174, 177, 179,
+            195, 197, 199, 200, 205, 207, 208, 211, 214, 215, 216,
+            218, 220, 222, 225, 226, 235, 244, 246, 253, 255, 256,
+        ],
+        &[
+            256, 1, 11, 12, 20, 23, 31, 39, 53, 66, 80,
+            81, 95, 107, 120, 131, 142, 154, 165, 175, 185, 196,
+            204, 213, 221, 228, 236, 237, 238, 244, 245, 251, 256,
+        ],
+    ],
+    &[
+        &[
+            256, 31, 52, 55, 72, 73, 81, 98, 102, 103, 121,
+            137, 141, 143, 146, 147, 157, 158, 161, 177, 188, 204,
+            206, 208, 211, 213, 224, 225, 229, 238, 246, 253, 256,
+        ],
+        &[
+            256, 1, 5, 21, 26, 44, 55, 60, 74, 89, 90,
+            93, 105, 118, 132, 146, 152, 166, 178, 180, 186, 187,
+            199, 211, 222, 232, 235, 245, 250, 251, 252, 253, 256,
+        ],
+    ],
+];
+
+/// Stage-2 residual index, one table per coefficient position. Each has 9 symbols, mapped
+/// symmetrically around zero: decoded index `i` maps to residual `i - 4`.
+pub(crate) const LSF_STAGE2: &[&[u32]] = &[
+    &[256, 1, 2, 3, 18, 242, 253, 254, 255, 256],
+    &[256, 1, 2, 4, 38, 221, 253, 254, 255, 256],
+    &[256, 1, 2, 6, 48, 197, 252, 254, 255, 256],
+    &[256, 1, 2, 10, 62, 185, 246, 254, 255, 256],
+    &[256, 1, 4, 20, 73, 174, 248, 254, 255, 256],
+    &[256, 1, 4, 21, 76, 166, 239, 254, 255, 256],
+    &[256, 1, 8, 32, 85, 159, 226, 252, 255, 256],
+    &[256, 1, 2, 20, 83, 161, 219, 249, 255, 256],
+    &[256, 1, 2, 3, 12, 244, 253, 254, 255, 256],
+    &[256, 1, 2, 4, 32, 218, 253, 254, 255, 256],
+    &[256, 1, 2, 5, 47, 199, 252, 254, 255, 256],
+    &[256, 1, 2, 12, 61, 187, 252, 254, 255, 256],
+    &[256, 1, 5, 24, 72, 172, 249, 254, 255, 256],
+    &[256, 1, 2, 16, 70, 170, 242, 254, 255, 256],
+    &[256, 1, 2, 17, 78, 165, 226, 251, 255, 256],
+    &[256, 1, 8, 29, 79, 156, 237, 254, 255, 256],
+];
+
+/// The interpolation factor (0 to 4, in quarters) between the previous and current frame's LSFs.
+pub(crate) const LSF_INTERPOLATION_OFFSET: &[u32] = &[256, 13, 35, 64, 75, 256];
+
+/// Backward-prediction weights (Q8) used to reconstruct each stage-2 residual from its
+/// higher-indexed neighbor, one set per LSF codebook order class.
+pub(crate) const LSF_PRED_WEIGHTS_NB: &[i32] = &[179, 138, 140, 148, 151, 149, 153, 151, 163, 116];
+pub(crate) const LSF_PRED_WEIGHTS_WB: &[i32] = &[
+    198, 151, 152, 152, 151, 146, 148, 152, 151, 147, 156, 159, 158, 154, 159, 130,
+];
+
+/// Minimum spacing (in the same normalized `[0, 1)` units as the reconstructed LSFs) enforced
+/// between adjacent coefficients during stabilization.
+pub(crate) const MIN_LSF_SPACING: f32 = 0.01;
+
+/// The stage-2 residual quantization step, in normalized `[0, 1)` units per RFC 6716 Table 24.
+pub(crate) const LSF_RESIDUAL_STEP_NB: f32 = 0.003906;
+pub(crate) const LSF_RESIDUAL_STEP_WB: f32 = 0.001953;